@@ -0,0 +1,3 @@
+//! Tower middleware and utilities for HTTP clients and servers.
+
+pub mod services;
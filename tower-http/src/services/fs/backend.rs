@@ -0,0 +1,138 @@
+use futures_util::{future::BoxFuture, Stream};
+use std::{future::Future, io, path::Path, sync::Arc, time::SystemTime};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite};
+
+/// A source of files that [`ServeDir`] can read from.
+///
+/// [`ServeDir`]: crate::services::ServeDir
+pub trait Backend: Clone + Send + Sync + 'static {
+    /// An open file handle returned by [`Backend::open`].
+    ///
+    /// Tied to [`Backend::Metadata`] via `File::Metadata = Self::Metadata` so that code
+    /// generic over a `Backend` (e.g. a decorator like `CachingBackend`) can use metadata
+    /// obtained from either [`Backend::metadata`] or an open [`File::metadata`]
+    /// interchangeably, without the two types having to be reconciled by hand.
+    type File: File<Metadata = Self::Metadata>;
+
+    /// Metadata about a path, returned by [`Backend::metadata`].
+    type Metadata: Metadata;
+
+    /// The future returned by [`Backend::open`].
+    type OpenFuture: Future<Output = io::Result<Self::File>> + Send + 'static;
+
+    /// The future returned by [`Backend::metadata`].
+    type MetadataFuture: Future<Output = io::Result<Self::Metadata>> + Send + 'static;
+
+    /// Open the file at `path` for reading.
+    fn open<P: AsRef<Path>>(&self, path: P) -> Self::OpenFuture;
+
+    /// Fetch metadata for the entry at `path`, without opening it.
+    fn metadata<P: AsRef<Path>>(&self, path: P) -> Self::MetadataFuture;
+
+    /// A stream of `(name, metadata)` entries directly inside the directory at `path`,
+    /// returned by [`Backend::read_dir`].
+    type ReadDirStream: Stream<Item = io::Result<(String, Self::Metadata)>> + Send + 'static;
+
+    /// List the entries directly inside the directory at `path`.
+    ///
+    /// The default implementation reports [`io::ErrorKind::Unsupported`], so backends
+    /// that have no natural notion of listing (e.g. ones backed by a single blob store
+    /// key namespace) don't have to implement it.
+    fn read_dir<P: AsRef<Path>>(&self, _path: P) -> BoxFuture<'static, io::Result<Self::ReadDirStream>> {
+        Box::pin(async move {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "this backend does not support directory listings",
+            ))
+        })
+    }
+}
+
+/// An open file handle produced by a [`Backend`].
+pub trait File: AsyncRead + AsyncSeek + Unpin + Send + 'static {
+    /// Metadata about this file, returned by [`File::metadata`].
+    type Metadata: Metadata;
+
+    /// The future returned by [`File::metadata`].
+    type MetadataFuture<'a>: Future<Output = io::Result<Self::Metadata>> + Send + 'a
+    where
+        Self: 'a;
+
+    /// Fetch metadata for this already-open file.
+    fn metadata(&self) -> Self::MetadataFuture<'_>;
+}
+
+/// Metadata about a file or directory, as returned by a [`Backend`].
+pub trait Metadata: Send + Sync + 'static {
+    /// Whether the entry is a directory.
+    fn is_dir(&self) -> bool;
+
+    /// The last modification time of the entry.
+    fn modified(&self) -> io::Result<SystemTime>;
+
+    /// The length, in bytes, of the entry. `0` for directories.
+    fn len(&self) -> u64;
+}
+
+impl<M: Metadata> Metadata for Arc<M> {
+    fn is_dir(&self) -> bool {
+        (**self).is_dir()
+    }
+
+    fn modified(&self) -> io::Result<SystemTime> {
+        (**self).modified()
+    }
+
+    fn len(&self) -> u64 {
+        (**self).len()
+    }
+}
+
+/// A companion to [`Backend`] for backends that can also be written to.
+///
+/// This is deliberately a separate trait rather than more methods on [`Backend`]: most
+/// backends (embedded assets, object storage mirrors, ...) are read-only, and should not
+/// have to implement `create`/`remove`/`rename` just to satisfy the trait.
+pub trait WritableBackend: Send + Sync + 'static {
+    /// A writer returned by [`WritableBackend::create`]. Implementations should make
+    /// writes land atomically, e.g. by writing to a temporary path and renaming into
+    /// place once the writer is shut down.
+    type Writer: AsyncWrite + Unpin + Send + 'static;
+
+    /// The future returned by [`WritableBackend::create`].
+    type CreateFuture: Future<Output = io::Result<Self::Writer>> + Send + 'static;
+
+    /// The future returned by [`WritableBackend::remove`].
+    type RemoveFuture: Future<Output = io::Result<()>> + Send + 'static;
+
+    /// The future returned by [`WritableBackend::rename`].
+    type RenameFuture: Future<Output = io::Result<()>> + Send + 'static;
+
+    /// Create (or overwrite) the file at `path`, returning a writer for its contents.
+    fn create<P: AsRef<Path>>(&self, path: P) -> Self::CreateFuture;
+
+    /// Remove the file at `path`.
+    fn remove<P: AsRef<Path>>(&self, path: P) -> Self::RemoveFuture;
+
+    /// Rename the file at `from` to `to`.
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> Self::RenameFuture;
+}
+
+impl<B: WritableBackend> WritableBackend for Arc<B> {
+    type Writer = B::Writer;
+    type CreateFuture = B::CreateFuture;
+    type RemoveFuture = B::RemoveFuture;
+    type RenameFuture = B::RenameFuture;
+
+    fn create<P: AsRef<Path>>(&self, path: P) -> Self::CreateFuture {
+        (**self).create(path)
+    }
+
+    fn remove<P: AsRef<Path>>(&self, path: P) -> Self::RemoveFuture {
+        (**self).remove(path)
+    }
+
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> Self::RenameFuture {
+        (**self).rename(from, to)
+    }
+}
@@ -0,0 +1,14 @@
+//! [`Backend`]-generic file-serving services: [`ServeDir`] (with an opt-in auto-index),
+//! [`WritableServeDir`] (`PUT`/`DELETE` on top of a [`WritableBackend`]), and the
+//! [`Backend`] implementations they can be pointed at.
+//!
+//! [`Backend`]: backend::Backend
+//! [`WritableBackend`]: backend::WritableBackend
+
+pub mod backend;
+pub(crate) mod sanitize;
+pub mod serve_dir;
+pub mod writable_serve_dir;
+
+pub use serve_dir::ServeDir;
+pub use writable_serve_dir::WritableServeDir;
@@ -0,0 +1,69 @@
+//! Request-path sanitization shared by [`ServeDir`] and [`WritableServeDir`].
+//!
+//! [`ServeDir`]: crate::services::fs::serve_dir::ServeDir
+//! [`WritableServeDir`]: crate::services::fs::writable_serve_dir::WritableServeDir
+
+use std::path::PathBuf;
+
+/// Percent-decode and validate a request path before it reaches a [`Backend`].
+///
+/// [`.`][std::path::Component::CurDir] and empty segments are dropped, any
+/// [`..`][std::path::Component::ParentDir] segment is rejected outright (rather than
+/// resolved away), and any segment containing a path separator after decoding is
+/// rejected too. Without this, a request like `GET /../../etc/passwd` or
+/// `PUT /../../etc/cron.d/x` would resolve outside the backend's root.
+///
+/// [`Backend`]: crate::services::fs::backend::Backend
+pub(crate) fn sanitize_request_path(req_path: &str) -> Option<PathBuf> {
+    let mut sanitized = PathBuf::new();
+    for raw_segment in req_path.split('/') {
+        let segment = percent_encoding::percent_decode_str(raw_segment)
+            .decode_utf8()
+            .ok()?;
+        if segment.is_empty() || segment == "." {
+            continue;
+        }
+        if segment == ".."
+            || segment.contains('/')
+            || segment.contains(std::path::MAIN_SEPARATOR)
+            || (std::path::MAIN_SEPARATOR != '\\' && segment.contains('\\'))
+        {
+            return None;
+        }
+        sanitized.push(&*segment);
+    }
+    Some(sanitized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_parent_dir_traversal() {
+        assert_eq!(sanitize_request_path("/../../etc/cron.d/x"), None);
+        assert_eq!(sanitize_request_path("/a/../../b"), None);
+        assert_eq!(sanitize_request_path("/a/b/.."), None);
+    }
+
+    #[test]
+    fn rejects_percent_encoded_traversal() {
+        assert_eq!(sanitize_request_path("/%2e%2e/%2e%2e/etc/passwd"), None);
+    }
+
+    #[test]
+    fn drops_dot_and_empty_segments() {
+        assert_eq!(
+            sanitize_request_path("/./a//b/./c"),
+            Some(PathBuf::from("a/b/c"))
+        );
+    }
+
+    #[test]
+    fn accepts_plain_relative_path() {
+        assert_eq!(
+            sanitize_request_path("/uploads/file.txt"),
+            Some(PathBuf::from("uploads/file.txt"))
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! HTML directory listings for [`ServeDir::auto_index`].
+//!
+//! [`ServeDir::auto_index(true)`][ServeDir::auto_index] makes [`ServeDir`] call
+//! [`render_index`] when a request resolves to a directory that has no `index.html`,
+//! instead of returning `404`.
+//!
+//! [`ServeDir`]: super::ServeDir
+//! [`ServeDir::auto_index`]: super::ServeDir::auto_index
+
+use crate::services::fs::backend::Metadata;
+use bytes::Bytes;
+use http::{header, Response};
+use http_body_util::Full;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::fmt::Write as _;
+
+/// Everything but ASCII alphanumerics and the URL-unreserved `-`, `.`, `_` and `~` is
+/// percent-encoded in generated `href`s.
+const HREF_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn escape_html(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render an HTML listing of `entries` found directly under `request_path`.
+///
+/// `entries` must already be the `(name, metadata)` pairs yielded by
+/// [`Backend::read_dir`](crate::services::fs::backend::Backend::read_dir); this function
+/// only sorts and renders them.
+pub(crate) fn render_index(
+    request_path: &str,
+    mut entries: Vec<(String, Box<dyn Metadata>)>,
+) -> Response<Full<Bytes>> {
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let request_path = escape_html(request_path);
+    let mut body = String::new();
+    let _ = write!(
+        body,
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Index of {request_path}</title></head>\n<body>\n<h1>Index of {request_path}</h1>\n<table>\n<tr><th>Name</th><th>Size</th><th>Last modified</th></tr>\n"
+    );
+
+    if request_path != "/" {
+        body.push_str("<tr><td><a href=\"../\">../</a></td><td>-</td><td></td></tr>\n");
+    }
+
+    for (name, metadata) in &entries {
+        let is_dir = metadata.is_dir();
+        let href = utf8_percent_encode(name, HREF_ENCODE_SET);
+        let display_name = escape_html(name);
+        let suffix = if is_dir { "/" } else { "" };
+        let size = if is_dir {
+            "-".to_owned()
+        } else {
+            metadata.len().to_string()
+        };
+        let modified = metadata
+            .modified()
+            .map(|time| httpdate::fmt_http_date(time))
+            .unwrap_or_default();
+
+        let _ = write!(
+            body,
+            "<tr><td><a href=\"{href}{suffix}\">{display_name}{suffix}</a></td><td>{size}</td><td>{modified}</td></tr>\n"
+        );
+    }
+
+    body.push_str("</table>\n</body>\n</html>\n");
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io, time::SystemTime};
+
+    struct StaticMetadata {
+        is_dir: bool,
+        len: u64,
+    }
+
+    impl Metadata for StaticMetadata {
+        fn is_dir(&self) -> bool {
+            self.is_dir
+        }
+
+        fn modified(&self) -> io::Result<SystemTime> {
+            Ok(SystemTime::UNIX_EPOCH)
+        }
+
+        fn len(&self) -> u64 {
+            self.len
+        }
+    }
+
+    async fn body_of(response: Response<Full<Bytes>>) -> String {
+        let bytes = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn escapes_dangerous_characters_in_names_and_path() {
+        let entries = vec![(
+            "<script>alert(1)</script>.html".to_owned(),
+            Box::new(StaticMetadata {
+                is_dir: false,
+                len: 0,
+            }) as Box<dyn Metadata>,
+        )];
+        let body = body_of(render_index("/\"><img src=x>/", entries)).await;
+
+        assert!(!body.contains("<script>"));
+        assert!(body.contains("&lt;script&gt;alert(1)&lt;/script&gt;.html"));
+        assert!(!body.contains("\"><img"));
+        assert!(body.contains("&quot;&gt;&lt;img src=x&gt;"));
+    }
+
+    #[tokio::test]
+    async fn sorts_entries_by_name() {
+        let entries = vec![
+            (
+                "b.txt".to_owned(),
+                Box::new(StaticMetadata {
+                    is_dir: false,
+                    len: 0,
+                }) as Box<dyn Metadata>,
+            ),
+            (
+                "a.txt".to_owned(),
+                Box::new(StaticMetadata {
+                    is_dir: false,
+                    len: 0,
+                }) as Box<dyn Metadata>,
+            ),
+            (
+                "c.txt".to_owned(),
+                Box::new(StaticMetadata {
+                    is_dir: false,
+                    len: 0,
+                }) as Box<dyn Metadata>,
+            ),
+        ];
+        let body = body_of(render_index("/", entries)).await;
+
+        let a = body.find("a.txt").unwrap();
+        let b = body.find("b.txt").unwrap();
+        let c = body.find("c.txt").unwrap();
+        assert!(a < b && b < c);
+    }
+
+    #[tokio::test]
+    async fn percent_encodes_hrefs_but_not_display_names() {
+        let entries = vec![(
+            "a b.txt".to_owned(),
+            Box::new(StaticMetadata {
+                is_dir: false,
+                len: 0,
+            }) as Box<dyn Metadata>,
+        )];
+        let body = body_of(render_index("/", entries)).await;
+
+        assert!(body.contains("href=\"a%20b.txt\""));
+        assert!(body.contains(">a b.txt<"));
+    }
+}
@@ -0,0 +1,402 @@
+//! A [`Backend`] decorator that single-flights concurrent opens of the same path.
+
+use crate::services::fs::backend::{Backend, File, Metadata};
+use futures_util::{future::BoxFuture, ready, stream::BoxStream, StreamExt};
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::{Hash, Hasher},
+    io::{self, SeekFrom},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{Context, Poll},
+};
+use tokio::{
+    fs::File as TempFile,
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncWriteExt, ReadBuf},
+    sync::{watch, Mutex},
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Progress {
+    available: u64,
+    finished: bool,
+    failed: bool,
+}
+
+struct Shared<B: Backend> {
+    temp_path: PathBuf,
+    progress: watch::Sender<Progress>,
+    metadata: Arc<B::Metadata>,
+}
+
+impl<B: Backend> Drop for Shared<B> {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.temp_path);
+    }
+}
+
+/// A [`Backend`] decorator that deduplicates concurrent opens of the same path.
+///
+/// If request `B` asks for a path that request `A` is already reading from the inner
+/// backend, `B` does not trigger a second inner [`Backend::open`]; both share the one
+/// in-progress read, which streams into a temporary file that every reader tails.
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct CachingBackend<B: Backend> {
+    inner: B,
+    cache_dir: Arc<PathBuf>,
+    inflight: Arc<Mutex<HashMap<PathBuf, Weak<Shared<B>>>>>,
+}
+
+impl<B: Backend> CachingBackend<B> {
+    /// Wrap `inner`, staging in-progress reads as files under `cache_dir`.
+    pub fn new(inner: B, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            cache_dir: Arc::new(cache_dir.into()),
+            inflight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+fn temp_path_for(cache_dir: &Path, path: &Path) -> PathBuf {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    path.hash(&mut hasher);
+    cache_dir.join(format!("{:016x}.part", hasher.finish()))
+}
+
+async fn produce<B: Backend>(mut inner_file: B::File, mut temp: TempFile, shared: Arc<Shared<B>>) {
+    let result: io::Result<()> = async {
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            let n = inner_file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            temp.write_all(&buf[..n]).await?;
+            // `tokio::fs::File` completes writes in the background; wait for them so
+            // readers never see a watermark past what is actually on disk.
+            temp.flush().await?;
+            shared.progress.send_modify(|p| p.available += n as u64);
+        }
+        Ok(())
+    }
+    .await;
+
+    shared.progress.send_modify(|p| match result {
+        Ok(()) => p.finished = true,
+        Err(_) => p.failed = true,
+    });
+}
+
+impl<B: Backend> Backend for CachingBackend<B> {
+    type File = CachingFile<B>;
+    type Metadata = Arc<B::Metadata>;
+    type OpenFuture = BoxFuture<'static, io::Result<Self::File>>;
+    type MetadataFuture = BoxFuture<'static, io::Result<Self::Metadata>>;
+    type ReadDirStream = BoxStream<'static, io::Result<(String, Self::Metadata)>>;
+
+    fn open<P: AsRef<Path>>(&self, path: P) -> Self::OpenFuture {
+        let path = path.as_ref().to_owned();
+        let this = self.clone();
+        Box::pin(async move {
+            // Held for the entire check-then-open-then-insert sequence (including the
+            // inner `open().await`), so two concurrent callers for the same path can
+            // never both become the producer. `tokio::sync::Mutex`'s guard is `Send`,
+            // unlike `std::sync::Mutex`'s, so this is sound to hold across an `.await`.
+            let mut inflight = this.inflight.lock().await;
+            inflight.retain(|_, weak| weak.strong_count() > 0);
+            let shared = if let Some(shared) = inflight.get(&path).and_then(Weak::upgrade) {
+                shared
+            } else {
+                let inner_file = this.inner.open(&path).await?;
+                let metadata = Arc::new(inner_file.metadata().await?);
+                let temp_path = temp_path_for(&this.cache_dir, &path);
+                // Created here rather than in `produce`, so the file exists before any
+                // `CachingFile::new` below tries to open it.
+                let temp = TempFile::create(&temp_path).await?;
+                let shared = Arc::new(Shared {
+                    temp_path,
+                    progress: watch::Sender::new(Progress::default()),
+                    metadata,
+                });
+                inflight.insert(path.clone(), Arc::downgrade(&shared));
+                tokio::spawn(produce::<B>(inner_file, temp, shared.clone()));
+                shared
+            };
+            drop(inflight);
+            CachingFile::new(shared).await
+        })
+    }
+
+    fn metadata<P: AsRef<Path>>(&self, path: P) -> Self::MetadataFuture {
+        let fut = self.inner.metadata(path);
+        Box::pin(async move { Ok(Arc::new(fut.await?)) })
+    }
+
+    fn read_dir<P: AsRef<Path>>(&self, path: P) -> BoxFuture<'static, io::Result<Self::ReadDirStream>> {
+        // Directory listings bypass the single-flight cache entirely and forward
+        // straight to the inner backend: it's the file *contents* we dedupe reads of,
+        // not metadata enumeration.
+        let fut = self.inner.read_dir(path);
+        Box::pin(async move {
+            let stream = fut.await?;
+            Ok(stream
+                .map(|entry| entry.map(|(name, metadata)| (name, Arc::new(metadata))))
+                .boxed())
+        })
+    }
+}
+
+type WaitFuture = Pin<Box<dyn Future<Output = (watch::Receiver<Progress>, Result<Progress, watch::error::RecvError>)> + Send>>;
+
+fn wait_for_change(mut rx: watch::Receiver<Progress>) -> WaitFuture {
+    Box::pin(async move {
+        let result = rx.changed().await.map(|_| *rx.borrow());
+        (rx, result)
+    })
+}
+
+/// A file handle reading the shared, on-disk tail of a [`CachingBackend`] read.
+#[non_exhaustive]
+pub struct CachingFile<B: Backend> {
+    shared: Arc<Shared<B>>,
+    reader: TempFile,
+    index: u64,
+    progress: watch::Receiver<Progress>,
+    waiting: Option<WaitFuture>,
+}
+
+impl<B: Backend> CachingFile<B> {
+    async fn new(shared: Arc<Shared<B>>) -> io::Result<Self> {
+        let reader = TempFile::open(&shared.temp_path).await?;
+        let progress = shared.progress.subscribe();
+        Ok(Self {
+            shared,
+            reader,
+            index: 0,
+            progress,
+            waiting: None,
+        })
+    }
+}
+
+impl<B: Backend> AsyncRead for CachingFile<B> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            if let Some(waiting) = self.waiting.as_mut() {
+                let (rx, result) = ready!(waiting.as_mut().poll(cx));
+                self.waiting = None;
+                self.progress = rx;
+                result.map_err(|_| {
+                    io::Error::new(io::ErrorKind::Other, "cache producer task was dropped")
+                })?;
+                continue;
+            }
+
+            let progress = *self.progress.borrow();
+            if progress.failed {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "cache producer failed to fetch this file",
+                )));
+            }
+
+            if self.index < progress.available {
+                let max = (progress.available - self.index) as usize;
+                let mut limited = buf.take(max);
+                ready!(Pin::new(&mut self.reader).poll_read(cx, &mut limited))?;
+                let n = limited.filled().len();
+                // SAFETY: `limited` only ever exposes the unfilled tail of `buf`, so the
+                // bytes it filled were already initialized as part of `buf`'s own buffer.
+                unsafe { buf.assume_init(n) };
+                buf.advance(n);
+                self.index += n as u64;
+                return Poll::Ready(Ok(()));
+            }
+
+            if progress.finished {
+                return Poll::Ready(Ok(()));
+            }
+
+            self.waiting = Some(wait_for_change(self.progress.clone()));
+        }
+    }
+}
+
+impl<B: Backend> AsyncSeek for CachingFile<B> {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let len = self.shared.metadata.len();
+        match position {
+            SeekFrom::Start(start) => self.index = start,
+            SeekFrom::End(end) => {
+                self.index = len.checked_add_signed(end).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "invalid seek to a negative or overflowing position",
+                    )
+                })?;
+            }
+            SeekFrom::Current(offset) => {
+                self.index = self.index.checked_add_signed(offset).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "invalid seek to a negative or overflowing position",
+                    )
+                })?;
+            }
+        }
+        let index = self.index;
+        Pin::new(&mut self.reader).start_seek(SeekFrom::Start(index))
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.reader).poll_complete(cx)
+    }
+}
+
+impl<B: Backend> File for CachingFile<B> {
+    type Metadata = Arc<B::Metadata>;
+    type MetadataFuture<'a> = BoxFuture<'a, io::Result<Self::Metadata>> where B: 'a;
+
+    fn metadata(&self) -> Self::MetadataFuture<'_> {
+        let metadata = self.shared.metadata.clone();
+        Box::pin(async move { Ok(metadata) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::BoxFuture;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+        time::Duration,
+    };
+    use tokio::io::AsyncReadExt;
+
+    #[derive(Debug, Clone)]
+    struct StaticMetadata(u64);
+
+    impl Metadata for StaticMetadata {
+        fn is_dir(&self) -> bool {
+            false
+        }
+
+        fn modified(&self) -> io::Result<std::time::SystemTime> {
+            Ok(std::time::SystemTime::UNIX_EPOCH)
+        }
+
+        fn len(&self) -> u64 {
+            self.0
+        }
+    }
+
+    struct StaticFile {
+        data: &'static [u8],
+        index: usize,
+    }
+
+    impl AsyncRead for StaticFile {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let remaining = &self.data[self.index..];
+            let n = remaining.len().min(buf.remaining());
+            buf.put_slice(&remaining[..n]);
+            self.index += n;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncSeek for StaticFile {
+        fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+            if let SeekFrom::Start(n) = position {
+                self.index = n as usize;
+            }
+            Ok(())
+        }
+
+        fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+            Poll::Ready(Ok(self.index as u64))
+        }
+    }
+
+    impl File for StaticFile {
+        type Metadata = StaticMetadata;
+        type MetadataFuture<'a> = BoxFuture<'a, io::Result<Self::Metadata>>;
+
+        fn metadata(&self) -> Self::MetadataFuture<'_> {
+            let len = self.data.len() as u64;
+            Box::pin(async move { Ok(StaticMetadata(len)) })
+        }
+    }
+
+    /// A `Backend` that counts how many times `open` was called, so tests can assert
+    /// concurrent requests for the same path were deduplicated into a single read.
+    #[derive(Clone)]
+    struct CountingBackend {
+        opens: Arc<AtomicUsize>,
+        contents: &'static [u8],
+    }
+
+    impl Backend for CountingBackend {
+        type File = StaticFile;
+        type Metadata = StaticMetadata;
+        type OpenFuture = BoxFuture<'static, io::Result<Self::File>>;
+        type MetadataFuture = BoxFuture<'static, io::Result<Self::Metadata>>;
+        type ReadDirStream = futures_util::stream::Empty<io::Result<(String, Self::Metadata)>>;
+
+        fn open<P: AsRef<Path>>(&self, _path: P) -> Self::OpenFuture {
+            self.opens.fetch_add(1, AtomicOrdering::SeqCst);
+            let data = self.contents;
+            Box::pin(async move {
+                // Simulate a slow upstream fetch so both concurrent callers are
+                // guaranteed to race on the same in-flight entry.
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(StaticFile { data, index: 0 })
+            })
+        }
+
+        fn metadata<P: AsRef<Path>>(&self, _path: P) -> Self::MetadataFuture {
+            let len = self.contents.len() as u64;
+            Box::pin(async move { Ok(StaticMetadata(len)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_opens_share_one_producer() {
+        let opens = Arc::new(AtomicUsize::new(0));
+        let inner = CountingBackend {
+            opens: opens.clone(),
+            contents: b"hello world",
+        };
+        let dir = std::env::temp_dir().join(format!(
+            "tower-http-caching-backend-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let backend = CachingBackend::new(inner, dir);
+
+        let (a, b) = tokio::join!(backend.open("file.txt"), backend.open("file.txt"));
+        let mut a = a.unwrap();
+        let mut b = b.unwrap();
+
+        let mut buf_a = Vec::new();
+        let mut buf_b = Vec::new();
+        a.read_to_end(&mut buf_a).await.unwrap();
+        b.read_to_end(&mut buf_b).await.unwrap();
+
+        assert_eq!(buf_a, b"hello world");
+        assert_eq!(buf_b, b"hello world");
+        assert_eq!(opens.load(AtomicOrdering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,350 @@
+//! A [`Backend`] decorator that transparently encrypts/decrypts files at rest with a
+//! seekable stream cipher, so `Range` requests against [`ServeDir`] keep working.
+//!
+//! [`ServeDir`]: crate::services::ServeDir
+
+use crate::services::fs::backend::{Backend, File, Metadata, WritableBackend};
+use chacha20::{
+    cipher::{KeyIvInit, StreamCipher, StreamCipherSeek},
+    XChaCha20,
+};
+use futures_util::{future::BoxFuture, ready, stream::BoxStream, StreamExt};
+use rand::RngCore;
+use std::{
+    io::{self, SeekFrom},
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+    time::SystemTime,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+fn invalid_seek() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "invalid seek to a negative or overflowing position",
+    )
+}
+
+/// A [`Backend`] decorator that stores files encrypted with XChaCha20 and decrypts them
+/// transparently on read.
+///
+/// A random 24-byte nonce is prepended to each stored file; [`Metadata::len`] reports the
+/// plaintext length (ciphertext length minus the nonce header). `XChaCha20` was chosen
+/// specifically because its keystream is seekable ([`StreamCipherSeek`]) — a cipher
+/// without that property cannot support range requests through this backend.
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct EncryptedBackend<B> {
+    inner: B,
+    key: [u8; KEY_LEN],
+}
+
+impl<B> EncryptedBackend<B> {
+    /// Wrap `inner`, encrypting/decrypting with `key`.
+    pub fn new(inner: B, key: [u8; KEY_LEN]) -> Self {
+        Self { inner, key }
+    }
+}
+
+impl<B: Backend> Backend for EncryptedBackend<B> {
+    type File = EncryptedFile<B::File>;
+    type Metadata = EncryptedMetadata<B::Metadata>;
+    type OpenFuture = BoxFuture<'static, io::Result<Self::File>>;
+    type MetadataFuture = BoxFuture<'static, io::Result<Self::Metadata>>;
+    type ReadDirStream = BoxStream<'static, io::Result<(String, Self::Metadata)>>;
+
+    fn open<P: AsRef<Path>>(&self, path: P) -> Self::OpenFuture {
+        let key = self.key;
+        let open = self.inner.open(path);
+        Box::pin(async move {
+            let mut inner = open.await?;
+            let mut nonce = [0u8; NONCE_LEN];
+            inner.read_exact(&mut nonce).await?;
+
+            let ciphertext_len = inner.metadata().await?.len();
+            let len = ciphertext_len.checked_sub(NONCE_LEN as u64).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "ciphertext is shorter than its nonce header",
+                )
+            })?;
+
+            let cipher = XChaCha20::new(&key.into(), &nonce.into());
+            Ok(EncryptedFile {
+                inner,
+                cipher,
+                index: 0,
+                len,
+            })
+        })
+    }
+
+    fn metadata<P: AsRef<Path>>(&self, path: P) -> Self::MetadataFuture {
+        let metadata = self.inner.metadata(path);
+        Box::pin(async move {
+            let inner = metadata.await?;
+            let len = inner.len().saturating_sub(NONCE_LEN as u64);
+            Ok(EncryptedMetadata { inner, len })
+        })
+    }
+
+    fn read_dir<P: AsRef<Path>>(&self, path: P) -> BoxFuture<'static, io::Result<Self::ReadDirStream>> {
+        let fut = self.inner.read_dir(path);
+        Box::pin(async move {
+            let stream = fut.await?;
+            Ok(stream
+                .map(|entry| {
+                    entry.map(|(name, inner)| {
+                        let len = inner.len().saturating_sub(NONCE_LEN as u64);
+                        (name, EncryptedMetadata { inner, len })
+                    })
+                })
+                .boxed())
+        })
+    }
+}
+
+impl<B: WritableBackend> WritableBackend for EncryptedBackend<B> {
+    type Writer = EncryptedWriter<B::Writer>;
+    type CreateFuture = BoxFuture<'static, io::Result<Self::Writer>>;
+    type RemoveFuture = B::RemoveFuture;
+    type RenameFuture = B::RenameFuture;
+
+    fn create<P: AsRef<Path>>(&self, path: P) -> Self::CreateFuture {
+        let key = self.key;
+        let create = self.inner.create(path);
+        Box::pin(async move {
+            let mut inner = create.await?;
+            let mut nonce = [0u8; NONCE_LEN];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            inner.write_all(&nonce).await?;
+            let cipher = XChaCha20::new(&key.into(), &nonce.into());
+            Ok(EncryptedWriter {
+                inner,
+                cipher,
+                pending: None,
+            })
+        })
+    }
+
+    fn remove<P: AsRef<Path>>(&self, path: P) -> Self::RemoveFuture {
+        self.inner.remove(path)
+    }
+
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> Self::RenameFuture {
+        self.inner.rename(from, to)
+    }
+}
+
+/// A file opened through an [`EncryptedBackend`]; `index`/`len` are in plaintext bytes.
+#[non_exhaustive]
+pub struct EncryptedFile<F> {
+    inner: F,
+    cipher: XChaCha20,
+    index: u64,
+    len: u64,
+}
+
+impl<F: AsyncRead + Unpin> AsyncRead for EncryptedFile<F> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        let fetched = &mut buf.filled_mut()[before..];
+        self.cipher.apply_keystream(fetched);
+        self.index += fetched.len() as u64;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<F: AsyncSeek + Unpin> AsyncSeek for EncryptedFile<F> {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let target = match position {
+            SeekFrom::Start(start) => start,
+            SeekFrom::End(end) => self.len.checked_add_signed(end).ok_or_else(invalid_seek)?,
+            SeekFrom::Current(offset) => {
+                self.index.checked_add_signed(offset).ok_or_else(invalid_seek)?
+            }
+        };
+        // Reposition the keystream to match the new plaintext offset (the ChaCha20
+        // block counter advances one block per 64 bytes, with any leading partial-block
+        // bytes discarded), then seek the underlying file past the nonce header.
+        self.cipher.seek(target);
+        self.index = target;
+        Pin::new(&mut self.inner).start_seek(SeekFrom::Start(NONCE_LEN as u64 + target))
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        ready!(Pin::new(&mut self.inner).poll_complete(cx))?;
+        Poll::Ready(Ok(self.index))
+    }
+}
+
+impl<F: File> File for EncryptedFile<F> {
+    type Metadata = EncryptedMetadata<F::Metadata>;
+    type MetadataFuture<'a> = BoxFuture<'a, io::Result<Self::Metadata>> where F: 'a;
+
+    fn metadata(&self) -> Self::MetadataFuture<'_> {
+        let metadata = self.inner.metadata();
+        let len = self.len;
+        Box::pin(async move { Ok(EncryptedMetadata { inner: metadata.await?, len }) })
+    }
+}
+
+/// Metadata for an entry served through an [`EncryptedBackend`]; `len` is the plaintext
+/// length, with the nonce header already subtracted.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct EncryptedMetadata<M> {
+    inner: M,
+    len: u64,
+}
+
+impl<M: Metadata> Metadata for EncryptedMetadata<M> {
+    fn is_dir(&self) -> bool {
+        self.inner.is_dir()
+    }
+
+    fn modified(&self) -> io::Result<SystemTime> {
+        self.inner.modified()
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+}
+
+/// A writer returned by [`EncryptedBackend::create`] (via [`WritableBackend`]).
+#[non_exhaustive]
+pub struct EncryptedWriter<W> {
+    inner: W,
+    cipher: XChaCha20,
+    // An already-encrypted chunk awaiting a full write, kept around across `Pending`
+    // returns: re-encrypting `buf` on retry would advance the keystream a second time
+    // and corrupt the ciphertext.
+    pending: Option<(Vec<u8>, usize)>,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for EncryptedWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        // Take the pending chunk out of `self` before borrowing `self.inner` below: keeping
+        // `chunk`/`written` borrowed from `self.pending` across the loop (as a prior version
+        // of this code did) conflicts with the `&mut self.inner` borrow the write needs.
+        let (mut chunk, mut written) = match self.pending.take() {
+            Some(pending) => pending,
+            None => {
+                let mut chunk = buf.to_vec();
+                self.cipher.apply_keystream(&mut chunk);
+                (chunk, 0)
+            }
+        };
+
+        loop {
+            if written == chunk.len() {
+                return Poll::Ready(Ok(buf.len()));
+            }
+            match Pin::new(&mut self.inner).poll_write(cx, &chunk[written..]) {
+                Poll::Ready(Ok(n)) => written += n,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => {
+                    self.pending = Some((chunk, written));
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::fs::serve_dir::tokio_backend::TokioBackend;
+    use tokio::io::AsyncSeekExt;
+
+    async fn roundtrip_backend() -> (EncryptedBackend<TokioBackend>, &'static str) {
+        let dir = std::env::temp_dir().join(format!(
+            "tower-http-encrypted-backend-test-{}-{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let inner = TokioBackend::new(dir);
+        let backend = EncryptedBackend::new(inner, [7u8; KEY_LEN]);
+
+        // Long enough to span several keystream blocks (ChaCha20 blocks are 64 bytes).
+        let contents = "0123456789".repeat(32);
+        let mut writer = backend.create("greeting.txt").await.unwrap();
+        writer.write_all(contents.as_bytes()).await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        (backend, Box::leak(contents.into_boxed_str()))
+    }
+
+    #[tokio::test]
+    async fn seeking_resyncs_the_keystream() {
+        let (backend, contents) = roundtrip_backend().await;
+
+        for &offset in &[0u64, 1, 63, 64, 65, 127, 200] {
+            let mut file = backend.open("greeting.txt").await.unwrap();
+            file.seek(SeekFrom::Start(offset)).await.unwrap();
+            let mut actual = Vec::new();
+            file.read_to_end(&mut actual).await.unwrap();
+
+            assert_eq!(actual, contents.as_bytes()[offset as usize..].to_vec());
+        }
+    }
+
+    #[tokio::test]
+    async fn seeking_backwards_after_reading_still_resyncs() {
+        let (backend, contents) = roundtrip_backend().await;
+
+        let mut file = backend.open("greeting.txt").await.unwrap();
+        let mut prefix = vec![0u8; 100];
+        file.read_exact(&mut prefix).await.unwrap();
+        assert_eq!(prefix, contents.as_bytes()[..100]);
+
+        file.seek(SeekFrom::Start(10)).await.unwrap();
+        let mut rest = Vec::new();
+        file.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, contents.as_bytes()[10..].to_vec());
+    }
+
+    #[tokio::test]
+    async fn ciphertext_on_disk_is_not_plaintext() {
+        let dir = std::env::temp_dir().join(format!(
+            "tower-http-encrypted-backend-test-{}-{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let inner = TokioBackend::new(dir.clone());
+        let backend = EncryptedBackend::new(inner, [7u8; KEY_LEN]);
+
+        let mut writer = backend.create("secret.txt").await.unwrap();
+        writer.write_all(b"hello world").await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        let on_disk = tokio::fs::read(dir.join("secret.txt")).await.unwrap();
+        assert_ne!(on_disk, b"hello world");
+        assert_eq!(on_disk.len(), NONCE_LEN + "hello world".len());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::services::fs::backend::{Backend, File, Metadata};
-use futures_util::{future::BoxFuture, ready};
+use futures_util::{future::BoxFuture, ready, stream};
 use std::{
     io::{self, SeekFrom},
     path::Path,
@@ -74,6 +74,37 @@ impl Backend for IncludeDirBackend {
                 })
         })
     }
+
+    type ReadDirStream = stream::Iter<std::vec::IntoIter<io::Result<(String, Self::Metadata)>>>;
+
+    fn read_dir<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> BoxFuture<'static, io::Result<Self::ReadDirStream>> {
+        let path = path.as_ref().to_owned();
+        let this = self.clone();
+        Box::pin(async move {
+            let dir = this.inner.get_dir(&path).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} is not a directory.", path.display()),
+                )
+            })?;
+            let entries = dir
+                .entries()
+                .iter()
+                .map(|entry| {
+                    let name = entry
+                        .path()
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    Ok((name, IncludeDirMetadata { entry: entry.clone() }))
+                })
+                .collect::<Vec<_>>();
+            Ok(stream::iter(entries))
+        })
+    }
 }
 
 impl AsyncRead for IncludeDirFile {
@@ -0,0 +1,240 @@
+//! [`Backend`] implementations, plus [`ServeDir`] itself: a [`Backend`]-generic file
+//! server with an opt-in directory-listing fallback.
+//!
+//! [`Backend`]: crate::services::fs::backend::Backend
+
+mod auto_index;
+pub mod caching;
+pub mod encrypted;
+pub mod include_dir;
+#[cfg(feature = "opendal")]
+pub mod opendal;
+pub mod tokio_backend;
+#[cfg(feature = "io-uring")]
+pub mod uring;
+
+use crate::services::fs::{
+    backend::{Backend, Metadata},
+    sanitize::sanitize_request_path,
+};
+use auto_index::render_index;
+use bytes::Bytes;
+use futures_util::TryStreamExt;
+use http::{header, Request, Response, StatusCode};
+use http_body_util::Full;
+use std::{
+    future::Future,
+    io::{self, SeekFrom},
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tower_service::Service;
+
+/// A [`Backend`]-generic file server: resolves the request path to a file (falling back
+/// to `index.html` for directories, and optionally to a generated listing via
+/// [`ServeDir::auto_index`]), and serves its contents with a `Content-Length` header.
+///
+/// A directory request whose path doesn't end in `/` is redirected (`301`) to the
+/// trailing-slash form first, since the generated listing and `index.html` pages use
+/// page-relative links. A `Range` request header is honoured with a `206 Partial Content`
+/// response over the backend's [`AsyncSeek`] impl; a range it can't satisfy gets `416`.
+///
+/// Unlike the plain filesystem-only `ServeDir`, this one works over anything a [`Backend`]
+/// impl wraps — the local filesystem, an embedded [`include_dir`], encrypted-at-rest
+/// storage, object storage, and so on.
+///
+/// [`AsyncSeek`]: tokio::io::AsyncSeek
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ServeDir<B> {
+    backend: Arc<B>,
+    auto_index: bool,
+}
+
+impl<B> ServeDir<B> {
+    /// Serve files through `backend`.
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            auto_index: false,
+        }
+    }
+
+    /// When a request resolves to a directory with no `index.html`, render an HTML
+    /// listing of its contents instead of returning `404`. Off by default.
+    ///
+    /// Listing a directory requires the backend's [`Backend::read_dir`] to be supported;
+    /// if it isn't, a directory with no `index.html` still falls back to `404`.
+    pub fn auto_index(mut self, enabled: bool) -> Self {
+        self.auto_index = enabled;
+        self
+    }
+}
+
+impl<B, ReqBody> Service<Request<ReqBody>> for ServeDir<B>
+where
+    B: Backend,
+{
+    type Response = Response<Full<Bytes>>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let backend = self.backend.clone();
+        let auto_index = self.auto_index;
+        let request_path = req.uri().path().to_owned();
+        let range_header = req
+            .headers()
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+        Box::pin(async move {
+            let path = match sanitize_request_path(&request_path) {
+                Some(path) => path,
+                None => return Ok(status_response(StatusCode::BAD_REQUEST)),
+            };
+
+            match backend.metadata(&path).await {
+                Ok(metadata) if metadata.is_dir() => {
+                    if !request_path.ends_with('/') {
+                        return Ok(redirect_with_trailing_slash(&request_path));
+                    }
+                    serve_dir_entry(
+                        &*backend,
+                        &path,
+                        &request_path,
+                        auto_index,
+                        range_header.as_deref(),
+                    )
+                    .await
+                }
+                Ok(_) => serve_file(&*backend, &path, range_header.as_deref()).await,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    Ok(status_response(StatusCode::NOT_FOUND))
+                }
+                Err(_) => Ok(status_response(StatusCode::INTERNAL_SERVER_ERROR)),
+            }
+        })
+    }
+}
+
+async fn serve_dir_entry<B: Backend>(
+    backend: &B,
+    path: &Path,
+    request_path: &str,
+    auto_index: bool,
+    range_header: Option<&str>,
+) -> io::Result<Response<Full<Bytes>>> {
+    let index_path = path.join("index.html");
+    match backend.metadata(&index_path).await {
+        Ok(_) => serve_file(backend, &index_path, range_header).await,
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            Ok(status_response(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+        Err(_) if !auto_index => Ok(status_response(StatusCode::NOT_FOUND)),
+        Err(_) => match backend.read_dir(path).await {
+            Ok(stream) => {
+                let entries: Vec<(String, Box<dyn Metadata>)> = stream
+                    .map_ok(|(name, metadata)| (name, Box::new(metadata) as Box<dyn Metadata>))
+                    .try_collect()
+                    .await?;
+                Ok(render_index(request_path, entries))
+            }
+            Err(_) => Ok(status_response(StatusCode::NOT_FOUND)),
+        },
+    }
+}
+
+async fn serve_file<B: Backend>(
+    backend: &B,
+    path: &Path,
+    range_header: Option<&str>,
+) -> io::Result<Response<Full<Bytes>>> {
+    let mut file = backend.open(path).await?;
+    let len = file.metadata().await?.len();
+
+    match range_header.map(|header| parse_range(header, len)) {
+        None => {
+            let mut contents = Vec::new();
+            file.read_to_end(&mut contents).await?;
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header(header::ACCEPT_RANGES, "bytes")
+                .header(header::CONTENT_LENGTH, contents.len())
+                .body(Full::new(Bytes::from(contents)))
+                .unwrap())
+        }
+        Some(Some((start, end))) => {
+            file.seek(SeekFrom::Start(start)).await?;
+            let mut contents = vec![0u8; (end - start + 1) as usize];
+            file.read_exact(&mut contents).await?;
+            Ok(Response::builder()
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::ACCEPT_RANGES, "bytes")
+                .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+                .header(header::CONTENT_LENGTH, contents.len())
+                .body(Full::new(Bytes::from(contents)))
+                .unwrap())
+        }
+        Some(None) => Ok(Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+            .body(Full::new(Bytes::new()))
+            .unwrap()),
+    }
+}
+
+/// Parse a `Range` request header of the form `bytes=start-end`, `bytes=start-`, or
+/// `bytes=-suffix_len` against a resource of length `len`, returning the inclusive
+/// `(start, end)` byte range to serve.
+///
+/// Returns `None` if the header is malformed, requests multiple ranges (not supported
+/// here), or is unsatisfiable against `len` — callers should respond `416` in that case.
+fn parse_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = header.strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+
+    if start.is_empty() {
+        let suffix_len: u64 = end.parse().ok()?;
+        if suffix_len == 0 || len == 0 {
+            return None;
+        }
+        return Some((len.saturating_sub(suffix_len), len - 1));
+    }
+
+    let start: u64 = start.parse().ok()?;
+    if start >= len {
+        return None;
+    }
+    let end = if end.is_empty() {
+        len - 1
+    } else {
+        end.parse::<u64>().ok()?.min(len - 1)
+    };
+    (end >= start).then_some((start, end))
+}
+
+fn redirect_with_trailing_slash(request_path: &str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::MOVED_PERMANENTLY)
+        .header(header::LOCATION, format!("{request_path}/"))
+        .body(Full::new(Bytes::new()))
+        .unwrap()
+}
+
+fn status_response(status: StatusCode) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::new()))
+        .unwrap()
+}
@@ -0,0 +1,245 @@
+//! The plain [`tokio::fs`]-backed [`Backend`], with an optional [`WritableBackend`] side.
+
+use crate::services::fs::backend::{Backend, File, Metadata, WritableBackend};
+use futures_util::{future::BoxFuture, ready, stream::BoxStream, StreamExt};
+use std::{
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::SystemTime,
+};
+use tokio::{
+    fs,
+    io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf},
+};
+
+/// A [`Backend`] that serves files straight from the local filesystem, rooted at a
+/// fixed directory.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TokioBackend {
+    root: Arc<PathBuf>,
+}
+
+impl TokioBackend {
+    /// Serve files from beneath `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Arc::new(root.into()),
+        }
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        self.root.join(path)
+    }
+}
+
+impl Backend for TokioBackend {
+    type File = TokioFile;
+    type Metadata = TokioMetadata;
+    type OpenFuture = BoxFuture<'static, io::Result<Self::File>>;
+    type MetadataFuture = BoxFuture<'static, io::Result<Self::Metadata>>;
+
+    fn open<P: AsRef<Path>>(&self, path: P) -> Self::OpenFuture {
+        let path = self.resolve(path.as_ref());
+        Box::pin(async move {
+            let inner = fs::File::open(path).await?;
+            Ok(TokioFile { inner })
+        })
+    }
+
+    fn metadata<P: AsRef<Path>>(&self, path: P) -> Self::MetadataFuture {
+        let path = self.resolve(path.as_ref());
+        Box::pin(async move { Ok(TokioMetadata(fs::metadata(path).await?)) })
+    }
+
+    type ReadDirStream = BoxStream<'static, io::Result<(String, Self::Metadata)>>;
+
+    fn read_dir<P: AsRef<Path>>(&self, path: P) -> BoxFuture<'static, io::Result<Self::ReadDirStream>> {
+        let path = self.resolve(path.as_ref());
+        Box::pin(async move {
+            let read_dir = fs::read_dir(path).await?;
+            let stream = tokio_stream::wrappers::ReadDirStream::new(read_dir).then(|entry| async move {
+                let entry = entry?;
+                let metadata = TokioMetadata(entry.metadata().await?);
+                Ok((entry.file_name().to_string_lossy().into_owned(), metadata))
+            });
+            Ok(stream.boxed())
+        })
+    }
+}
+
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+impl WritableBackend for TokioBackend {
+    type Writer = TokioWriter;
+    type CreateFuture = BoxFuture<'static, io::Result<Self::Writer>>;
+    type RemoveFuture = BoxFuture<'static, io::Result<()>>;
+    type RenameFuture = BoxFuture<'static, io::Result<()>>;
+
+    fn create<P: AsRef<Path>>(&self, path: P) -> Self::CreateFuture {
+        let final_path = self.resolve(path.as_ref());
+        Box::pin(async move {
+            let n = TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+            let mut temp_name = final_path.clone().into_os_string();
+            temp_name.push(format!(".{n:x}.part"));
+            let temp_path = PathBuf::from(temp_name);
+            let file = fs::File::create(&temp_path).await?;
+            Ok(TokioWriter {
+                file: Some(file),
+                temp_path,
+                final_path,
+                renaming: None,
+                renamed: false,
+            })
+        })
+    }
+
+    fn remove<P: AsRef<Path>>(&self, path: P) -> Self::RemoveFuture {
+        let path = self.resolve(path.as_ref());
+        Box::pin(async move { fs::remove_file(path).await })
+    }
+
+    fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> Self::RenameFuture {
+        let from = self.resolve(from.as_ref());
+        let to = self.resolve(to.as_ref());
+        Box::pin(async move { fs::rename(from, to).await })
+    }
+}
+
+/// A file opened through a [`TokioBackend`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TokioFile {
+    inner: fs::File,
+}
+
+impl AsyncRead for TokioFile {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncSeek for TokioFile {
+    fn start_seek(mut self: Pin<&mut Self>, position: io::SeekFrom) -> io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
+impl File for TokioFile {
+    type Metadata = TokioMetadata;
+    type MetadataFuture<'a> = BoxFuture<'a, io::Result<Self::Metadata>>;
+
+    fn metadata(&self) -> Self::MetadataFuture<'_> {
+        Box::pin(async move { Ok(TokioMetadata(self.inner.metadata().await?)) })
+    }
+}
+
+/// Metadata for an entry served through a [`TokioBackend`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TokioMetadata(std::fs::Metadata);
+
+impl Metadata for TokioMetadata {
+    fn is_dir(&self) -> bool {
+        self.0.is_dir()
+    }
+
+    fn modified(&self) -> io::Result<SystemTime> {
+        self.0.modified()
+    }
+
+    fn len(&self) -> u64 {
+        if self.0.is_dir() {
+            0
+        } else {
+            self.0.len()
+        }
+    }
+}
+
+/// A writer returned by [`TokioBackend::create`].
+///
+/// Writes go to a sibling temporary file; the final rename into place happens as part
+/// of [`AsyncWrite::poll_shutdown`], so callers must shut the writer down for the write
+/// to become visible.
+#[non_exhaustive]
+pub struct TokioWriter {
+    file: Option<fs::File>,
+    temp_path: PathBuf,
+    final_path: PathBuf,
+    renaming: Option<BoxFuture<'static, io::Result<()>>>,
+    renamed: bool,
+}
+
+impl Drop for TokioWriter {
+    fn drop(&mut self) {
+        // If the writer is dropped before `poll_shutdown` renamed the temp file into
+        // place (e.g. the caller bailed out of `write_body` on an I/O error partway
+        // through the request body), the temp file would otherwise be left behind
+        // forever next to `final_path`.
+        if !self.renamed {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+impl AsyncWrite for TokioWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.file.as_mut() {
+            Some(file) => Pin::new(file).poll_write(cx, buf),
+            None => Poll::Ready(Err(already_shut_down())),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.file.as_mut() {
+            Some(file) => Pin::new(file).poll_flush(cx),
+            None => Poll::Ready(Err(already_shut_down())),
+        }
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            if let Some(renaming) = self.renaming.as_mut() {
+                let result = ready!(renaming.as_mut().poll(cx));
+                self.renaming = None;
+                self.renamed = result.is_ok();
+                return Poll::Ready(result);
+            }
+
+            match self.file.as_mut() {
+                Some(file) => {
+                    ready!(Pin::new(file).poll_shutdown(cx))?;
+                    self.file = None;
+                    let temp_path = self.temp_path.clone();
+                    let final_path = self.final_path.clone();
+                    self.renaming = Some(Box::pin(fs::rename(temp_path, final_path)));
+                }
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+fn already_shut_down() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "writer has already been shut down")
+}
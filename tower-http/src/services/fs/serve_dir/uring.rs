@@ -0,0 +1,318 @@
+//! An [`io-uring`]-backed [`Backend`] for serving files without going through the
+//! blocking threadpool.
+//!
+//! [`io-uring`]: https://en.wikipedia.org/wiki/Io_uring
+
+use crate::services::fs::backend::{Backend, File, Metadata};
+use futures_util::{future::BoxFuture, ready};
+use std::{
+    collections::HashMap,
+    future::Future,
+    io::{self, SeekFrom},
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    io::{AsyncRead, AsyncSeek, ReadBuf},
+    sync::{mpsc, oneshot},
+};
+
+enum Command {
+    Open {
+        path: PathBuf,
+        reply: oneshot::Sender<io::Result<(u64, UringMetadata)>>,
+    },
+    Read {
+        id: u64,
+        offset: u64,
+        len: usize,
+        reply: oneshot::Sender<io::Result<Vec<u8>>>,
+    },
+    Metadata {
+        path: PathBuf,
+        reply: oneshot::Sender<io::Result<UringMetadata>>,
+    },
+    Stat {
+        id: u64,
+        reply: oneshot::Sender<io::Result<UringMetadata>>,
+    },
+    Close {
+        id: u64,
+    },
+}
+
+fn worker_gone() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "io_uring worker thread has shut down")
+}
+
+/// A [`Backend`] that serves files from the local filesystem via `io_uring`, using a
+/// dedicated [`tokio_uring`] runtime instead of `tokio::fs`'s blocking threadpool.
+///
+/// Every [`UringBackend`] owns a single worker thread that parks the `io_uring`
+/// instance; open files and in-flight reads are addressed over a channel so that
+/// [`UringFile`] itself stays `Send` and can be driven from any runtime.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UringBackend {
+    tx: mpsc::UnboundedSender<Command>,
+}
+
+impl UringBackend {
+    /// Spawn a new `io_uring` worker thread and return a backend connected to it.
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        std::thread::Builder::new()
+            .name("tower-http-io-uring".into())
+            .spawn(move || run_worker(rx))
+            .expect("failed to spawn io_uring worker thread");
+        Self { tx }
+    }
+}
+
+impl Default for UringBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn run_worker(mut rx: mpsc::UnboundedReceiver<Command>) {
+    tokio_uring::start(async move {
+        let mut open_files: HashMap<u64, tokio_uring::fs::File> = HashMap::new();
+        let mut next_id: u64 = 0;
+        while let Some(cmd) = rx.recv().await {
+            match cmd {
+                Command::Open { path, reply } => {
+                    let result = open_and_stat(&path).await;
+                    let _ = reply.send(result.map(|(file, metadata)| {
+                        let id = next_id;
+                        next_id += 1;
+                        open_files.insert(id, file);
+                        (id, metadata)
+                    }));
+                }
+                Command::Read {
+                    id,
+                    offset,
+                    len,
+                    reply,
+                } => {
+                    let result = match open_files.get(&id) {
+                        Some(file) => {
+                            let (res, buf) = file.read_at(vec![0u8; len], offset).await;
+                            res.map(|n| {
+                                let mut buf = buf;
+                                buf.truncate(n);
+                                buf
+                            })
+                        }
+                        None => Err(io::Error::new(io::ErrorKind::NotFound, "file is closed")),
+                    };
+                    let _ = reply.send(result);
+                }
+                Command::Metadata { path, reply } => {
+                    let result = async {
+                        let (file, metadata) = open_and_stat(&path).await?;
+                        file.close().await?;
+                        Ok(metadata)
+                    }
+                    .await;
+                    let _ = reply.send(result);
+                }
+                Command::Stat { id, reply } => {
+                    let result = match open_files.get(&id) {
+                        Some(file) => file.statx().await.map(|stat| UringMetadata::from_statx(&stat)),
+                        None => Err(io::Error::new(io::ErrorKind::NotFound, "file is closed")),
+                    };
+                    let _ = reply.send(result);
+                }
+                Command::Close { id } => {
+                    if let Some(file) = open_files.remove(&id) {
+                        let _ = file.close().await;
+                    }
+                }
+            }
+        }
+    });
+}
+
+async fn open_and_stat(path: &Path) -> io::Result<(tokio_uring::fs::File, UringMetadata)> {
+    let file = tokio_uring::fs::File::open(path).await?;
+    let stat = file.statx().await?;
+    Ok((file, UringMetadata::from_statx(&stat)))
+}
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct UringMetadata {
+    len: u64,
+    is_dir: bool,
+    modified: SystemTime,
+}
+
+impl UringMetadata {
+    fn from_statx(stat: &libc::statx) -> Self {
+        let modified = UNIX_EPOCH
+            + Duration::new(stat.stx_mtime.tv_sec as u64, stat.stx_mtime.tv_nsec);
+        let is_dir = (stat.stx_mode as u32 & libc::S_IFMT) == libc::S_IFDIR;
+        Self {
+            len: if is_dir { 0 } else { stat.stx_size },
+            is_dir,
+            modified,
+        }
+    }
+}
+
+impl Metadata for UringMetadata {
+    fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    fn modified(&self) -> io::Result<SystemTime> {
+        Ok(self.modified)
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+}
+
+impl Backend for UringBackend {
+    type File = UringFile;
+    type Metadata = UringMetadata;
+    type OpenFuture = BoxFuture<'static, io::Result<Self::File>>;
+    type MetadataFuture = BoxFuture<'static, io::Result<Self::Metadata>>;
+    type ReadDirStream = futures_util::stream::Empty<io::Result<(String, Self::Metadata)>>;
+
+    fn open<P: AsRef<Path>>(&self, path: P) -> Self::OpenFuture {
+        let path = path.as_ref().to_owned();
+        let tx = self.tx.clone();
+        Box::pin(async move {
+            let (reply, rx) = oneshot::channel();
+            tx.send(Command::Open { path, reply }).map_err(|_| worker_gone())?;
+            let (id, metadata) = rx.await.map_err(|_| worker_gone())??;
+            Ok(UringFile {
+                tx,
+                id,
+                index: 0,
+                len: metadata.len,
+                pending_read: None,
+            })
+        })
+    }
+
+    fn metadata<P: AsRef<Path>>(&self, path: P) -> Self::MetadataFuture {
+        let path = path.as_ref().to_owned();
+        let tx = self.tx.clone();
+        Box::pin(async move {
+            let (reply, rx) = oneshot::channel();
+            tx.send(Command::Metadata { path, reply }).map_err(|_| worker_gone())?;
+            rx.await.map_err(|_| worker_gone())?
+        })
+    }
+}
+
+/// A file opened through a [`UringBackend`].
+///
+/// Reads are proxied to the backend's `io_uring` worker thread, at the offset held in
+/// the local `index` field; seeks only move that field, mirroring [`IncludeDirFile`]'s
+/// `AsyncSeek` contract so `ServeDir`'s range-request handling works unchanged.
+///
+/// [`IncludeDirFile`]: super::include_dir::IncludeDirFile
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct UringFile {
+    tx: mpsc::UnboundedSender<Command>,
+    id: u64,
+    index: u64,
+    len: u64,
+    pending_read: Option<oneshot::Receiver<io::Result<Vec<u8>>>>,
+}
+
+impl Drop for UringFile {
+    fn drop(&mut self) {
+        let _ = self.tx.send(Command::Close { id: self.id });
+    }
+}
+
+impl AsyncRead for UringFile {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            if let Some(rx) = self.pending_read.as_mut() {
+                let result = ready!(Pin::new(rx).poll(cx)).map_err(|_| worker_gone())?;
+                self.pending_read = None;
+                let data = result?;
+                self.index += data.len() as u64;
+                buf.put_slice(&data);
+                return Poll::Ready(Ok(()));
+            }
+
+            if self.index >= self.len {
+                return Poll::Ready(Ok(()));
+            }
+
+            let want = buf.remaining().min((self.len - self.index) as usize);
+            let (reply, rx) = oneshot::channel();
+            self.tx
+                .send(Command::Read {
+                    id: self.id,
+                    offset: self.index,
+                    len: want,
+                    reply,
+                })
+                .map_err(|_| worker_gone())?;
+            self.pending_read = Some(rx);
+        }
+    }
+}
+
+impl AsyncSeek for UringFile {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        self.pending_read = None;
+        match position {
+            SeekFrom::Start(start) => self.index = start,
+            SeekFrom::End(end) => {
+                self.index = self.len.checked_add_signed(end).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "invalid seek to a negative or overflowing position",
+                    )
+                })?;
+            }
+            SeekFrom::Current(offset) => {
+                self.index = self.index.checked_add_signed(offset).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "invalid seek to a negative or overflowing position",
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.index))
+    }
+}
+
+impl File for UringFile {
+    type Metadata = UringMetadata;
+    type MetadataFuture<'a> = BoxFuture<'a, io::Result<Self::Metadata>>;
+
+    fn metadata(&self) -> Self::MetadataFuture<'_> {
+        let tx = self.tx.clone();
+        let id = self.id;
+        Box::pin(async move {
+            let (reply, rx) = oneshot::channel();
+            tx.send(Command::Stat { id, reply }).map_err(|_| worker_gone())?;
+            rx.await.map_err(|_| worker_gone())?
+        })
+    }
+}
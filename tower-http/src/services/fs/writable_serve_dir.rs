@@ -0,0 +1,129 @@
+//! A [`ServeDir`]-adjacent service that additionally serves `PUT`/`DELETE` through a
+//! [`WritableBackend`].
+//!
+//! [`ServeDir`]: crate::services::ServeDir
+
+use crate::services::fs::{backend::WritableBackend, sanitize::sanitize_request_path};
+use bytes::Bytes;
+use http::{Method, Request, Response, StatusCode};
+use http_body_util::BodyExt;
+use std::{
+    future::Future,
+    io,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::io::AsyncWriteExt;
+use tower_service::Service;
+
+/// Wraps an inner [`Service`] (typically [`ServeDir`]) so that `PUT` requests create or
+/// overwrite a file through a [`WritableBackend`], and `DELETE` requests remove one. Any
+/// other method is forwarded to the inner service untouched.
+///
+/// [`ServeDir`]: crate::services::ServeDir
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct WritableServeDir<B, S> {
+    backend: Arc<B>,
+    inner: S,
+}
+
+impl<B, S> WritableServeDir<B, S> {
+    /// Serve `PUT`/`DELETE` through `backend`, forwarding everything else to `inner`.
+    pub fn new(backend: B, inner: S) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            inner,
+        }
+    }
+}
+
+impl<B, S, ReqBody, ResBody> Service<Request<ReqBody>> for WritableServeDir<B, S>
+where
+    B: WritableBackend,
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: http_body::Body<Data = Bytes> + Send + Unpin + 'static,
+    ResBody: http_body::Body<Data = Bytes> + Default + Send + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        match *req.method() {
+            Method::PUT => {
+                let path = match sanitize_request_path(req.uri().path()) {
+                    Some(path) => path,
+                    None => return Box::pin(async move { Ok(bad_request_response()) }),
+                };
+                let backend = self.backend.clone();
+                Box::pin(async move { Ok(put_response(write_body(&backend, &path, req.into_body()).await)) })
+            }
+            Method::DELETE => {
+                let path = match sanitize_request_path(req.uri().path()) {
+                    Some(path) => path,
+                    None => return Box::pin(async move { Ok(bad_request_response()) }),
+                };
+                let backend = self.backend.clone();
+                Box::pin(async move { Ok(delete_response(backend.remove(&path).await)) })
+            }
+            _ => {
+                let mut inner = self.inner.clone();
+                Box::pin(async move { inner.call(req).await })
+            }
+        }
+    }
+}
+
+fn bad_request_response<ResBody: Default>() -> Response<ResBody> {
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(ResBody::default())
+        .unwrap()
+}
+
+async fn write_body<B: WritableBackend>(
+    backend: &B,
+    path: &Path,
+    mut body: impl http_body::Body<Data = Bytes> + Unpin,
+) -> io::Result<()> {
+    let mut writer = backend.create(path).await?;
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|_| io::Error::new(io::ErrorKind::Other, "error reading request body"))?;
+        if let Ok(data) = frame.into_data() {
+            writer.write_all(&data).await?;
+        }
+    }
+    writer.shutdown().await
+}
+
+fn put_response<ResBody: Default>(result: io::Result<()>) -> Response<ResBody> {
+    let status = if result.is_ok() {
+        StatusCode::CREATED
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    Response::builder()
+        .status(status)
+        .body(ResBody::default())
+        .unwrap()
+}
+
+fn delete_response<ResBody: Default>(result: io::Result<()>) -> Response<ResBody> {
+    let status = match result {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    Response::builder()
+        .status(status)
+        .body(ResBody::default())
+        .unwrap()
+}
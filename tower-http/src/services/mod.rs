@@ -0,0 +1,7 @@
+//! Tower [`Service`]s for serving static content.
+//!
+//! [`Service`]: tower_service::Service
+
+pub mod fs;
+
+pub use fs::{ServeDir, WritableServeDir};